@@ -1,5 +1,5 @@
 mod tree;
-use tree::{Player, Board, Result, GameState, StateIndex, Tree};
+use tree::{Player, Board, Result, GameState, StateIndex, Tree, TreeNode, Outcome};
 
 use egui::{Color32, FontId, TextFormat};
 use egui_graphs::{SettingsNavigation};
@@ -22,12 +22,33 @@ fn main() -> eframe::Result<()> {
 
 
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Random,
+    Minimax(u32),
+    Mcts(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Explore,
+    SinglePlayer { ai_side: Player, difficulty: Difficulty },
+    LocalMultiplayer,
+}
+
 pub struct BasicApp {
     tree: Tree,
+    outcomes: std::collections::HashMap<StateIndex, Outcome>,
     graph: egui_graphs::Graph,
     zoom_pan: bool,
     state_node_map: BisetMap<StateIndex, NodeIndex>,
     hovered_node: NodeIndex,
+    nav_selected: Option<NodeIndex>,
+
+    mode: Mode,
+    play_board: Board,
+    play_turn: Player,
+    play_result: Result,
 }
 
 impl BasicApp {
@@ -69,8 +90,12 @@ impl BasicApp {
         println!("Pruned to {} nodes", &win_nodes.iter().filter(|&n| *n).count());
         tree.prune_to_win_nodes(&win_nodes);
         println!("Pruning took {:?}", now.elapsed());
-        
-        
+
+        let now = time::Instant::now();
+        let outcomes = tree.solve();
+        println!("Solving took {:?}", now.elapsed());
+
+
         let mut state_node_map = BisetMap::new();
         let mut rng = rand::rng();
 
@@ -78,34 +103,6 @@ impl BasicApp {
         let dig = StableDiGraph::new();
         let mut graph = egui_graphs::Graph::from(&dig);
 
-    
-        // Recursively add nodes and edges, reusing nodes for duplicate states
-        fn add_to_graph(
-            tree: &Tree,
-            state_index: &StateIndex,
-            graph: &mut egui_graphs::Graph,
-            state_node_map: &mut BisetMap<StateIndex, NodeIndex>,
-            rng: &mut impl rand::Rng,
-        ) -> NodeIndex {
-            if let Some(&idx) = state_node_map.get(state_index).first() {
-                // println!("Found existing node for state: {}", tree[state_index]);
-                return idx;
-            }
-
-            // Random initial position
-            let pos = Pos2::new(
-                rng.random_range(-100.0..100.0),
-                rng.random_range(-100.0..100.0),
-            );
-            let idx = graph.add_node_with_location((), pos);
-            state_node_map.insert(state_index.clone(), idx);
-            for child in tree.iter_children(state_index).choose_multiple(rng, rand::random_range(1..=3)) {
-                let child_idx = add_to_graph(tree, child, graph, state_node_map, rng);
-                graph.add_edge(idx, child_idx, ());
-            }
-            idx
-        }
-
         let now = time::Instant::now();
         add_to_graph(&tree, &tree.root_index, &mut graph, &mut state_node_map, &mut rng);
         println!("Graph generating took {:?}", now.elapsed());
@@ -115,64 +112,356 @@ impl BasicApp {
 
         Self {
             tree,
+            outcomes,
             graph,
             zoom_pan: false,
             state_node_map,
             hovered_node: root_node,
+            nav_selected: None,
+
+            mode: Mode::Explore,
+            play_board: Board::empty(),
+            play_turn: Player::Red,
+            play_result: Result::Ongoing,
         }
     }
+
+    // Renders the clickable play board and handles a human's column click.
+    fn show_play_board(&mut self, ui: &mut egui::Ui) {
+        let human_turn = match self.mode {
+            Mode::SinglePlayer { ai_side, .. } => self.play_turn != ai_side,
+            _ => true,
+        };
+
+        ui.horizontal(|ui| {
+            for col in 0..BOARD_SIZE.0 {
+                let legal = self.play_result == Result::Ongoing && self.play_board.try_play(col, self.play_turn).is_some();
+                if ui.add_enabled(legal && human_turn, egui::Button::new(format!("{}", col))).clicked() {
+                    self.play_board.play(col, self.play_turn);
+                    self.play_result = tree::Bitboard::from_board(&self.play_board).result();
+                    self.play_turn = self.play_turn.flip();
+                }
+            }
+        });
+
+        ui.add_space(8.0);
+        ui.label(board_to_layout_job(&self.play_board));
+
+        match self.play_result {
+            Result::Win(p) => { ui.colored_label(player_color(p), format!("{} wins!", p)); }
+            Result::Draw => { ui.label("It's a draw!"); }
+            Result::Ongoing => { ui.label(format!("{}'s turn", self.play_turn)); }
+        }
+
+        if ui.button("Reset game").clicked() {
+            self.play_board = Board::empty();
+            self.play_turn = Player::Red;
+            self.play_result = Result::Ongoing;
+        }
+    }
+
+    // If it's the AI's turn and the game isn't over, plays its move.
+    fn maybe_play_ai_move(&mut self) {
+        let Mode::SinglePlayer { ai_side, difficulty } = self.mode else { return };
+        if self.play_result != Result::Ongoing || self.play_turn != ai_side {
+            return;
+        }
+        let col = ai_move(&self.play_board, self.play_turn, difficulty);
+        self.play_board.play(col, self.play_turn);
+        self.play_result = tree::Bitboard::from_board(&self.play_board).result();
+        self.play_turn = self.play_turn.flip();
+    }
+
+    // Replaces the explored tree and rebuilds the graph/outcome data that's derived from it.
+    fn load_tree(&mut self, tree: Tree) {
+        let mut state_node_map = BisetMap::new();
+        let mut rng = rand::rng();
+        let mut graph = egui_graphs::Graph::from(&StableDiGraph::new());
+        add_to_graph(&tree, &tree.root_index, &mut graph, &mut state_node_map, &mut rng);
+        let root_node = state_node_map.get(&tree.root_index).first().unwrap().to_owned();
+
+        self.outcomes = tree.solve();
+        self.tree = tree;
+        self.graph = graph;
+        self.state_node_map = state_node_map;
+        self.hovered_node = root_node;
+        self.nav_selected = None;
+    }
+
+    // The column that continues the current node's principal variation: the
+    // child whose outcome is best for whoever is to move at the current node.
+    fn pv_child(&self, state: &StateIndex) -> Option<(usize, StateIndex)> {
+        self.tree
+            .children_with_columns(state)
+            .filter_map(|(col, child)| Some((col, child, self.outcomes.get(&child)?.rank())))
+            .max_by_key(|&(_, _, rank)| -rank) // a child's rank is from its own mover's perspective, i.e. the opponent's
+            .map(|(col, child, _)| (col, child))
+    }
+
+    // Keeps exactly the graph node for the navigation cursor's current state
+    // marked as selected, so it's highlighted in the Explore view.
+    fn sync_nav_highlight(&mut self) {
+        let target = self.state_node_map.get(&self.tree.current).first().copied();
+        if target == self.nav_selected {
+            return;
+        }
+        if let Some(idx) = self.nav_selected {
+            if let Some(node) = self.graph.node_mut(idx) {
+                node.set_selected(false);
+            }
+        }
+        if let Some(idx) = target {
+            if let Some(node) = self.graph.node_mut(idx) {
+                node.set_selected(true);
+            }
+        }
+        self.nav_selected = target;
+    }
 }
 
-impl App for BasicApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // let mut widget = egui_graphs::DefaultGraphView::new(&mut self.g);
-            // ui.add(&mut widget);
+// Recursively add nodes and edges, reusing nodes for duplicate states.
+fn add_to_graph(
+    tree: &Tree,
+    state_index: &StateIndex,
+    graph: &mut egui_graphs::Graph,
+    state_node_map: &mut BisetMap<StateIndex, NodeIndex>,
+    rng: &mut impl rand::Rng,
+) -> NodeIndex {
+    if let Some(&idx) = state_node_map.get(state_index).first() {
+        // println!("Found existing node for state: {}", tree[state_index]);
+        return idx;
+    }
 
-            type L = egui_graphs::LayoutForceDirected<egui_graphs::FruchtermanReingoldWithCenterGravity>;
-            type S = egui_graphs::FruchtermanReingoldWithCenterGravityState;
+    // Random initial position
+    let pos = Pos2::new(
+        rng.random_range(-100.0..100.0),
+        rng.random_range(-100.0..100.0),
+    );
+    let idx = graph.add_node_with_location((), pos);
+    state_node_map.insert(state_index.clone(), idx);
+    // MCTS-grown subtrees carry visit counts; show them as a label so
+    // heavily-visited (more trustworthy) nodes stand out in the graph.
+    if let Some(node) = graph.node_mut(idx) {
+        node.set_label(format!("{}", tree[state_index].visits));
+    }
+    for child in tree.iter_children(state_index).choose_multiple(rng, rand::random_range(1..=3)) {
+        let child_idx = add_to_graph(tree, child, graph, state_node_map, rng);
+        graph.add_edge(idx, child_idx, ());
+    }
+    idx
+}
 
+fn player_color(player: Player) -> Color32 {
+    match player {
+        Player::Red => Color32::RED,
+        Player::Yellow => Color32::YELLOW,
+        Player::Empty => Color32::GRAY,
+    }
+}
 
-            let mut widget = egui_graphs::GraphView::<_,_,_,_,_,_,S,L>::new(&mut self.graph)
-                .with_navigations(&SettingsNavigation::new()
-                    .with_zoom_and_pan_enabled(self.zoom_pan)
-                    .with_fit_to_screen_enabled(!self.zoom_pan)
-            );
-            ui.add(&mut widget);
+// Picks a column for `turn` to play on `board` according to `difficulty`.
+fn ai_move(board: &Board, turn: Player, difficulty: Difficulty) -> usize {
+    match difficulty {
+        Difficulty::Random => random_legal_move(board, turn),
+
+        Difficulty::Minimax(depth) => {
+            let game = GameState::from_board(board.clone(), turn);
+            let mut tree = Tree::from_root(&game);
+            tree.explore(depth);
+            let outcomes = tree.solve();
+            let root = tree.root_index;
+            let mirrored = root_is_mirrored(&tree, &root, board);
+            let col = (0..BOARD_SIZE.0)
+                .filter_map(|col| {
+                    let child = tree.child_for_column(&root, col)?;
+                    Some((col, outcomes.get(&child)?.rank()))
+                })
+                .max_by_key(|&(_, rank)| -rank) // a child's rank is from its own mover's perspective, i.e. the opponent's
+                .map(|(col, _)| unmirror_column(col, mirrored));
+            // `solve` leaves a node undetermined rather than guess, so a quiet
+            // position can resolve none of the root's children; fall back to
+            // a random legal move instead of refusing to play.
+            col.unwrap_or_else(|| random_legal_move(board, turn))
+        }
 
-            // // Forceâ€‘Directed (FR) #with Center Gravity
-            // type L = egui_graphs::LayoutForceDirected<egui_graphs::FruchtermanReingoldWithCenterGravity>;
-            // type S = egui_graphs::FruchtermanReingoldWithCenterGravityState;
-            // let mut view = egui_graphs::GraphView::<_,_,_,_,_,_,S,L>::new(&mut self.g);
-            // ui.add(&mut view);
-        });
+        Difficulty::Mcts(iterations) => {
+            let game = GameState::from_board(board.clone(), turn);
+            let mut tree = Tree::from_root(&game);
+            tree.mcts(iterations, std::f32::consts::SQRT_2);
+            let root = tree.root_index;
+            let mirrored = root_is_mirrored(&tree, &root, board);
+            let col = (0..BOARD_SIZE.0)
+                .filter_map(|col| {
+                    let child = tree.child_for_column(&root, col)?;
+                    Some((col, tree[&child].visits))
+                })
+                .max_by_key(|&(_, visits)| visits)
+                .map(|(col, _)| unmirror_column(col, mirrored));
+            col.unwrap_or_else(|| random_legal_move(board, turn))
+        }
+    }
+}
+
+fn random_legal_move(board: &Board, turn: Player) -> usize {
+    (0..BOARD_SIZE.0)
+        .filter(|&col| board.try_play(col, turn).is_some())
+        .choose(&mut rand::rng())
+        .expect("AI has no legal move on a non-terminal board")
+}
+
+// Whether `tree`'s actual root node ended up stored mirrored relative to
+// `board`. `Tree::insert` canonicalizes via `Bitboard::canonical()`, which
+// compares raw bit patterns rather than `Board::canonical()`'s lexicographic
+// grid compare, so the two can disagree about which orientation is
+// "canonical" — re-deriving the flag from a separate `Board::canonical()`
+// call can point at the wrong column. Reading the tree's own stored board
+// back out sidesteps that mismatch entirely.
+fn root_is_mirrored(tree: &Tree, root: &StateIndex, board: &Board) -> bool {
+    tree.get_board(root).0 != board.0
+}
+
+// Maps a column chosen against the tree's actual root orientation back to
+// `board`'s own orientation, undoing whatever left-right mirror it was
+// stored under.
+fn unmirror_column(col: usize, mirrored: bool) -> usize {
+    if mirrored { BOARD_SIZE.0 - 1 - col } else { col }
+}
+
+impl App for BasicApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         egui::SidePanel::right("settings").show(ctx, |ui| {
             ui.label("Settings");
             ui.separator();
-            ui.add(egui::Checkbox::new(&mut self.zoom_pan, "Zoom/Pan"));
-
-            // ui.horizontal_centered(|ui| {
-            //         ui.vertical_centered(|ui| {
-            //             let texter = self.game.children[1].children[2].children[1].children[0].children[0].to_string();
-            //             ui.label(egui::RichText::new(texter).size(20.0).color(Color32::RED));
-            //         })
-            //     }
-            // )
-
-            let hovered = self.graph.hovered_node();
-            if let Some(idx) = hovered {
-                if idx != self.hovered_node {
-                    self.hovered_node = idx;
-                }
-            }
 
-            if let Some(key) = self.state_node_map.rev_get(&self.hovered_node).first() {
-                ui.label(board_to_layout_job(self.tree.get_board(key)));
+            ui.radio_value(&mut self.mode, Mode::Explore, "Explore");
+            ui.radio_value(&mut self.mode, Mode::LocalMultiplayer, "Local multiplayer");
+            ui.radio_value(
+                &mut self.mode,
+                Mode::SinglePlayer { ai_side: Player::Yellow, difficulty: Difficulty::Random },
+                "Single player",
+            );
+            if let Mode::SinglePlayer { ai_side, difficulty } = &mut self.mode {
+                ui.horizontal(|ui| {
+                    ui.label("AI plays:");
+                    ui.radio_value(ai_side, Player::Red, "Red");
+                    ui.radio_value(ai_side, Player::Yellow, "Yellow");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Difficulty:");
+                    ui.radio_value(difficulty, Difficulty::Random, "Random");
+                    ui.radio_value(difficulty, Difficulty::Minimax(9), "Minimax");
+                    ui.radio_value(difficulty, Difficulty::Mcts(500), "MCTS");
+                });
+            }
+            ui.separator();
 
-            } else {
-                ui.label("Hovered Node Key: None");
+            match self.mode {
+                Mode::Explore => {
+                    ui.add(egui::Checkbox::new(&mut self.zoom_pan, "Zoom/Pan"));
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Save tree").clicked() {
+                            match self.tree.to_json() {
+                                Ok(json) => {
+                                    if let Err(e) = std::fs::write("tree.json", json) {
+                                        eprintln!("Failed to save tree.json: {e}");
+                                    }
+                                }
+                                Err(e) => eprintln!("Failed to serialize tree: {e}"),
+                            }
+                        }
+                        if ui.button("Load tree").clicked() {
+                            match std::fs::read_to_string("tree.json").map(|s| Tree::from_json(&s)) {
+                                Ok(Ok(tree)) => self.load_tree(tree),
+                                Ok(Err(e)) => eprintln!("Failed to parse tree.json: {e}"),
+                                Err(e) => eprintln!("Failed to read tree.json: {e}"),
+                            }
+                        }
+                    });
+
+                    let hovered = self.graph.hovered_node();
+                    if let Some(idx) = hovered {
+                        if idx != self.hovered_node {
+                            self.hovered_node = idx;
+                        }
+                    }
+
+                    if let Some(key) = self.state_node_map.rev_get(&self.hovered_node).first() {
+                        ui.label(board_to_layout_job(&self.tree.get_board(key)));
+
+                        if let Some(outcome) = self.outcomes.get(key) {
+                            let (text, color) = outcome_label(&self.tree[key], outcome);
+                            ui.colored_label(color, text);
+                        }
+
+                        if let Some(line) = self.tree.export_line(*key) {
+                            if ui.button("Copy line").clicked() {
+                                ui.ctx().copy_text(line);
+                            }
+                        }
+                    } else {
+                        ui.label("Hovered Node Key: None");
+                    }
+
+                    ui.separator();
+                    ui.label("Review");
+                    ui.horizontal(|ui| {
+                        if ui.button("Reset").clicked() {
+                            self.tree.reset_to_root();
+                        }
+                        if ui.button("Prev").clicked() {
+                            self.tree.go_to_parent();
+                        }
+                        let pv = self.pv_child(&self.tree.current);
+                        if ui.add_enabled(pv.is_some(), egui::Button::new("Next")).clicked() {
+                            if let Some((col, _)) = pv {
+                                self.tree.go_to_child(col);
+                            }
+                        }
+                    });
+
+                    let current = self.tree.current;
+                    ui.label(board_to_layout_job(&self.tree.get_board(&current)));
+                    for (col, child) in self.tree.children_with_columns(&current).collect::<Vec<_>>() {
+                        let label = match self.outcomes.get(&child) {
+                            Some(outcome) => outcome_label(&self.tree[&child], outcome).0,
+                            None => "unsolved".to_owned(),
+                        };
+                        if ui.button(format!("{col}: {label}")).clicked() {
+                            self.tree.go_to_child(col);
+                        }
+                    }
+
+                    self.sync_nav_highlight();
+                }
+                Mode::SinglePlayer { .. } | Mode::LocalMultiplayer => {
+                    ui.label("Play the board in the center panel.");
+                }
             }
         });
+
+        match self.mode {
+            Mode::Explore => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    type L = egui_graphs::LayoutForceDirected<egui_graphs::FruchtermanReingoldWithCenterGravity>;
+                    type S = egui_graphs::FruchtermanReingoldWithCenterGravityState;
+
+                    let mut widget = egui_graphs::GraphView::<_,_,_,_,_,_,S,L>::new(&mut self.graph)
+                        .with_navigations(&SettingsNavigation::new()
+                            .with_zoom_and_pan_enabled(self.zoom_pan)
+                            .with_fit_to_screen_enabled(!self.zoom_pan)
+                    );
+                    ui.add(&mut widget);
+                });
+            }
+            Mode::SinglePlayer { .. } | Mode::LocalMultiplayer => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.heading("Connect 4");
+                    self.show_play_board(ui);
+                });
+                self.maybe_play_ai_move();
+            }
+        }
     }
 }
 
@@ -212,6 +501,15 @@ fn board_to_layout_job(board: &Board) -> LayoutJob {
 
 
 
+// Renders a solved node's value (who wins, and in how many plies) for the side panel.
+fn outcome_label(node: &TreeNode, outcome: &Outcome) -> (String, Color32) {
+    match outcome.value_for(node.turn) {
+        Result::Win(Player::Red) => (format!("Red wins in {}", outcome.distance()), Color32::RED),
+        Result::Win(Player::Yellow) => (format!("Yellow wins in {}", outcome.distance()), Color32::YELLOW),
+        _ => ("Draw".to_owned(), Color32::GRAY),
+    }
+}
+
 fn prune_to_wins(tree: &Tree, nodes: &mut Vec<bool>, state: &StateIndex) -> bool {
     let mut this_one = match tree[state].result {
         Result::Win(_) => true,
@@ -263,5 +561,11 @@ impl Tree {
             self.root_index = crate::tree::StateIndex(0);
         }
         self.nodes = new_nodes;
+        // `table` indexes the old `nodes` by board, so every entry is now
+        // either stale or pointing at the wrong index — rebuild it.
+        self.rebuild_table();
+        // Pruning only ever runs before any navigation happens, so just
+        // snap the cursor back to the (possibly remapped) root.
+        self.reset_to_root();
     }
 }
\ No newline at end of file
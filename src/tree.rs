@@ -21,7 +21,7 @@ pub enum Player {
 }
 
 impl Player {
-    fn flip(&self) -> Self {
+    pub fn flip(&self) -> Self {
         match self {
             Player::Red => Player::Yellow,
             Player::Yellow => Player::Red,
@@ -61,6 +61,10 @@ impl IndexMut<usize> for Board {
 }
 
 impl Board {
+    pub fn empty() -> Self {
+        empty_board()
+    }
+
     // Returns the canonical (lexicographically smallest) of the board and its mirror
     pub fn canonical(&self) -> Self {
         let original = &self.0;
@@ -77,6 +81,77 @@ impl Board {
         }
         panic!("Column {} is full", col);
     }
+
+    // Like `play`, but returns a new board instead of panicking when `col` is full.
+    pub fn try_play(&self, col: usize, player: Player) -> Option<Board> {
+        let mut next = self.clone();
+        for y in 0..BOARD_SIZE.1 {
+            if next[col][y] == Player::Empty {
+                next[col][y] = player;
+                return Some(next);
+            }
+        }
+        None
+    }
+
+    /// Encodes this position as a string of played columns, alternating Red
+    /// and Yellow from an empty board (e.g. `"3233245424"`). A final board
+    /// doesn't remember the order its columns were interleaved in, so this
+    /// reconstructs *some* legal move sequence reaching it, not necessarily
+    /// the one actually played; pair with `Tree::export_line` when the real
+    /// order matters.
+    pub fn to_moves(&self) -> Option<String> {
+        let heights: Vec<usize> = (0..BOARD_SIZE.0)
+            .map(|col| (0..BOARD_SIZE.1).take_while(|&row| self[col][row] != Player::Empty).count())
+            .collect();
+        let total = heights.iter().sum();
+
+        let mut next_row = vec![0usize; BOARD_SIZE.0];
+        let mut moves = String::new();
+        self.to_moves_from(&heights, total, 0, &mut next_row, &mut moves).then_some(moves)
+    }
+
+    // Greedily assigning each ply to the first column whose next cell matches
+    // can dead-end on boards that are genuinely reachable through legal play,
+    // since an earlier ply's arbitrary choice can block a column a later ply
+    // needed — so this backtracks, trying each plausible column for `i`
+    // before committing to it.
+    fn to_moves_from(&self, heights: &[usize], total: usize, i: usize, next_row: &mut Vec<usize>, moves: &mut String) -> bool {
+        if i == total {
+            return true;
+        }
+        let expected = if i % 2 == 0 { Player::Red } else { Player::Yellow };
+        for col in 0..BOARD_SIZE.0 {
+            if next_row[col] >= heights[col] || self[col][next_row[col]] != expected {
+                continue;
+            }
+            next_row[col] += 1;
+            moves.push_str(&col.to_string());
+            if self.to_moves_from(heights, total, i + 1, next_row, moves) {
+                return true;
+            }
+            moves.pop();
+            next_row[col] -= 1;
+        }
+        false
+    }
+
+    /// Replays a move string from `to_moves` onto an empty board, alternating
+    /// Red and Yellow starting with Red. Fails on an out-of-range column, a
+    /// full column, or a non-digit character.
+    pub fn from_moves(moves: &str) -> Option<Board> {
+        let mut board = Board::empty();
+        let mut turn = Player::Red;
+        for ch in moves.chars() {
+            let col = ch.to_digit(10)? as usize;
+            if col >= BOARD_SIZE.0 {
+                return None;
+            }
+            board = board.try_play(col, turn)?;
+            turn = turn.flip();
+        }
+        Some(board)
+    }
 }
 
 impl PartialEq for Board {
@@ -119,6 +194,152 @@ pub fn empty_board() -> Board {
     Board(grid)
 }
 
+// One row of headroom per column above the playable BOARD_SIZE.1 rows. That
+// sentinel row is never set, so the vertical and diagonal win-check shifts
+// (steps 1 and `BB_STRIDE` +/- 1) can never join the top of one column to
+// the bottom of the neighboring one into a false four in a row.
+const BB_STRIDE: u32 = (BOARD_SIZE.1 + 1) as u32;
+
+/// A packed representation of a position: one `u64` per player's stones,
+/// column-major with a sentinel row per column (bit `col * BB_STRIDE + row`).
+/// `Board`/`Grid` remain the API everything outside `Tree` renders and
+/// serializes against; `Bitboard` is the hot-path form `Tree` explores,
+/// solves, and rolls out MCTS on, since a handful of shift/and ops beats
+/// scanning all 42 cells on every node.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Bitboard {
+    red: u64,
+    yellow: u64,
+}
+
+impl Bitboard {
+    pub fn empty() -> Self {
+        Bitboard { red: 0, yellow: 0 }
+    }
+
+    pub fn from_board(board: &Board) -> Self {
+        let mut bb = Bitboard::empty();
+        for col in 0..BOARD_SIZE.0 {
+            for row in 0..BOARD_SIZE.1 {
+                match board[col][row] {
+                    Player::Red => bb.red |= Self::bit(col, row),
+                    Player::Yellow => bb.yellow |= Self::bit(col, row),
+                    Player::Empty => {}
+                }
+            }
+        }
+        bb
+    }
+
+    pub fn to_board(&self) -> Board {
+        let mut board = Board::empty();
+        for col in 0..BOARD_SIZE.0 {
+            for row in 0..BOARD_SIZE.1 {
+                let bit = Self::bit(col, row);
+                board[col][row] = if self.red & bit != 0 {
+                    Player::Red
+                } else if self.yellow & bit != 0 {
+                    Player::Yellow
+                } else {
+                    Player::Empty
+                };
+            }
+        }
+        board
+    }
+
+    fn bit(col: usize, row: usize) -> u64 {
+        1u64 << (col as u32 * BB_STRIDE + row as u32)
+    }
+
+    fn occupied(&self) -> u64 {
+        self.red | self.yellow
+    }
+
+    // The bit for the next empty cell in `col`, or `None` if it's full.
+    fn drop_bit(&self, col: usize) -> Option<u64> {
+        let col_base = col as u32 * BB_STRIDE;
+        let col_mask = ((1u64 << BOARD_SIZE.1) - 1) << col_base;
+        let height = ((self.occupied() & col_mask) >> col_base).count_ones();
+        if height as usize >= BOARD_SIZE.1 {
+            None
+        } else {
+            Some(1u64 << (col_base + height))
+        }
+    }
+
+    pub fn try_play(&self, col: usize, player: Player) -> Option<Bitboard> {
+        let bit = self.drop_bit(col)?;
+        let mut next = *self;
+        match player {
+            Player::Red => next.red |= bit,
+            Player::Yellow => next.yellow |= bit,
+            Player::Empty => panic!("can't play as Player::Empty"),
+        }
+        Some(next)
+    }
+
+    pub fn result(&self) -> Result {
+        if Self::has_four(self.red) {
+            return Result::Win(Player::Red);
+        }
+        if Self::has_four(self.yellow) {
+            return Result::Win(Player::Yellow);
+        }
+        if self.occupied() == Self::full_mask() {
+            return Result::Draw;
+        }
+        Result::Ongoing
+    }
+
+    fn full_mask() -> u64 {
+        let col_mask = (1u64 << BOARD_SIZE.1) - 1;
+        (0..BOARD_SIZE.0).fold(0u64, |acc, col| acc | (col_mask << (col as u32 * BB_STRIDE)))
+    }
+
+    // Vertical (step 1), horizontal (step `BB_STRIDE`, i.e. the same row one
+    // column over), and both diagonals (step `BB_STRIDE` +/- 1) all reduce to
+    // the same test: AND a bitboard with itself shifted by `step` to collapse
+    // adjacent-pairs, then do it again at `2 * step` to find four in a row.
+    fn has_four(bits: u64) -> bool {
+        for step in [1, BB_STRIDE, BB_STRIDE - 1, BB_STRIDE + 1] {
+            let pairs = bits & (bits >> step);
+            if pairs & (pairs >> (2 * step)) != 0 {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// The canonical (lexicographically smaller) of this position and its
+    /// left-right mirror, used as the transposition key in `Tree`.
+    pub fn canonical(&self) -> Bitboard {
+        let mirrored = self.mirror();
+        if (self.red, self.yellow) <= (mirrored.red, mirrored.yellow) {
+            *self
+        } else {
+            mirrored
+        }
+    }
+
+    // Reverses column order (column-bit reversal), leaving each column's own bits untouched.
+    fn mirror(&self) -> Bitboard {
+        Bitboard {
+            red: Self::mirror_bits(self.red),
+            yellow: Self::mirror_bits(self.yellow),
+        }
+    }
+
+    fn mirror_bits(bits: u64) -> u64 {
+        let col_mask = (1u64 << BB_STRIDE) - 1;
+        (0..BOARD_SIZE.0).fold(0u64, |acc, col| {
+            let mirrored_col = BOARD_SIZE.0 - 1 - col;
+            let chunk = (bits >> (col as u32 * BB_STRIDE)) & col_mask;
+            acc | (chunk << (mirrored_col as u32 * BB_STRIDE))
+        })
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Result {
     Win(Player),
@@ -310,6 +531,587 @@ pub fn find_children(
 
 
 
+/// An index into a `Tree`'s node arena. Stable for the lifetime of the tree
+/// (nodes are only ever appended, never removed, except by `prune_to_win_nodes`
+/// which remaps every index it keeps).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct StateIndex(pub usize);
+
+/// A starting position to build a `Tree` from: a board plus whose turn it is.
+#[derive(Debug, Clone)]
+pub struct GameState {
+    pub board: Board,
+    pub turn: Player,
+}
+
+impl GameState {
+    pub fn from_board(board: Board, turn: Player) -> Self {
+        GameState { board, turn }
+    }
+}
+
+/// One position in a `Tree`, addressed by `StateIndex`. `children` holds the
+/// indices of the positions reachable by a single legal move that have been
+/// linked in so far (by `explore`, MCTS expansion, or what pruning kept), in
+/// the order they were linked; a frontier node has none. `board` is the packed `Bitboard` form; use
+/// `Tree::get_board` for a `Board` view when you need to render or serialize it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeNode {
+    pub board: Bitboard,
+    pub turn: Player,
+    pub result: Result,
+    pub children: Vec<StateIndex>,
+    pub index: Option<StateIndex>,
+    pub visits: u32,
+    pub wins: f32,
+}
+
+impl TreeNode {
+    fn from_state(board: Bitboard, turn: Player) -> Self {
+        let result = board.result();
+        TreeNode {
+            board,
+            turn,
+            result,
+            children: Vec::new(),
+            index: None,
+            visits: 0,
+            wins: 0.0,
+        }
+    }
+}
+
+impl Display for TreeNode {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.board.to_board())?;
+        write!(f, "Goes to {}, ", self.children.len())?;
+        match self.result {
+            Result::Ongoing => write!(f, "its {}'s turn", self.turn),
+            Result::Win(p) => write!(f, "{} won!", p),
+            Result::Draw => write!(f, "impressive they drew waow"),
+        }
+    }
+}
+
+// Columns nearest the center are far more likely to be relevant to the
+// eventual result, so `explore` links children in this order, which gives
+// the solver's alpha-beta cutoffs a better move order to work with.
+const COLUMN_ORDER: [usize; 7] = [3, 2, 4, 1, 5, 0, 6];
+
+/// The solved game-theoretic value of a position, from the perspective of
+/// the player to move there, plus how many plies away that result is under
+/// optimal play (so a solver can prefer the fastest win or the slowest loss).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Win(u32),
+    Draw,
+    Loss(u32),
+}
+
+impl Outcome {
+    fn value(&self) -> i32 {
+        match self {
+            Outcome::Win(_) => 1,
+            Outcome::Draw => 0,
+            Outcome::Loss(_) => -1,
+        }
+    }
+
+    pub fn distance(&self) -> u32 {
+        match self {
+            Outcome::Win(d) | Outcome::Loss(d) => *d,
+            Outcome::Draw => 0,
+        }
+    }
+
+    /// A single `i64` score where higher is always better for the side to move:
+    /// wins beat draws beat losses, fastest wins and slowest losses are preferred
+    /// within a tier. Handy for picking a move with `max_by_key`.
+    pub fn rank(&self) -> i64 {
+        let tier = self.value() as i64 * 1_000_000;
+        match self {
+            Outcome::Win(d) => tier - *d as i64,
+            Outcome::Loss(d) => tier + *d as i64,
+            Outcome::Draw => tier,
+        }
+    }
+
+    /// Converts a `turn`-relative outcome into an absolute `Result`, e.g. for display.
+    pub fn value_for(&self, turn: Player) -> Result {
+        match self {
+            Outcome::Win(_) => Result::Win(turn),
+            Outcome::Loss(_) => Result::Win(turn.flip()),
+            Outcome::Draw => Result::Draw,
+        }
+    }
+
+    // Flips a child's outcome into its parent's perspective, one ply further out.
+    fn negate(self) -> Outcome {
+        match self {
+            Outcome::Win(d) => Outcome::Loss(d + 1),
+            Outcome::Loss(d) => Outcome::Win(d + 1),
+            Outcome::Draw => Outcome::Draw,
+        }
+    }
+
+    /// Like `rank`, but anchored to `depth` (plies from the search root)
+    /// rather than distance from this node. `rank` alone isn't safe to use
+    /// as an alpha-beta bound across recursion levels: `negate` always adds
+    /// exactly one ply to `distance`, so a child's `rank` and its parent's
+    /// `rank` differ by a non-constant offset. Anchoring to `depth` instead
+    /// scores every node by its absolute ply-to-result, which is the same
+    /// number seen from a node or any of its ancestors, so `score_at` negates
+    /// exactly (`parent.score_at(d) == -child.score_at(d + 1)`) and alpha-beta
+    /// can safely prune on it without hiding a same-tier but faster mate.
+    fn score_at(&self, depth: i64) -> i64 {
+        const MATE: i64 = 1_000_000;
+        let ply_to_result = depth + self.distance() as i64;
+        match self {
+            Outcome::Win(_) => MATE - ply_to_result,
+            Outcome::Loss(_) => ply_to_result - MATE,
+            Outcome::Draw => 0,
+        }
+    }
+
+    // True if `self` is at least as good an outcome as `other` for the side choosing between them,
+    // preferring the fastest win or the slowest loss when the values tie.
+    fn at_least_as_good_as(&self, other: &Outcome) -> bool {
+        match self.value().cmp(&other.value()) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => match self {
+                Outcome::Win(_) => self.distance() <= other.distance(),
+                Outcome::Loss(_) => self.distance() >= other.distance(),
+                Outcome::Draw => true,
+            },
+        }
+    }
+}
+
+// The JSON shape of a saved `Tree`: everything but the transposition table,
+// which is just an index over `nodes` and gets rebuilt on load.
+#[derive(Serialize)]
+struct TreeExport<'a> {
+    nodes: &'a [TreeNode],
+    root_index: StateIndex,
+}
+
+#[derive(Deserialize)]
+struct OwnedTreeExport {
+    nodes: Vec<TreeNode>,
+    root_index: StateIndex,
+}
+
+/// The deduplicated game graph rooted at a `GameState`. Nodes are shared
+/// across transpositions (any two move orders reaching the same canonical
+/// board collapse to the same `StateIndex`), so `nodes.len()` is the count
+/// of *unique* reachable positions, not the count of move sequences.
+pub struct Tree {
+    pub nodes: Vec<TreeNode>,
+    pub root_index: StateIndex,
+    table: HashMap<Bitboard, StateIndex>,
+    /// Where a reviewer navigating this tree currently is, plus the
+    /// breadcrumb stack of ancestors actually walked to get there (needed
+    /// since transpositions give a node more than one possible parent).
+    pub current: StateIndex,
+    path: Vec<StateIndex>,
+}
+
+impl Tree {
+    pub fn from_root(game: &GameState) -> Self {
+        let mut tree = Tree {
+            nodes: Vec::new(),
+            root_index: StateIndex(0),
+            table: HashMap::new(),
+            current: StateIndex(0),
+            path: Vec::new(),
+        };
+        let root_board = Bitboard::from_board(&game.board);
+        tree.root_index = tree.insert(root_board, game.turn);
+        tree.current = tree.root_index;
+        tree
+    }
+
+    // Finds or creates the node for `board`'s canonical form, under `turn` to move.
+    fn insert(&mut self, board: Bitboard, turn: Player) -> StateIndex {
+        let canonical = board.canonical();
+        if let Some(&idx) = self.table.get(&canonical) {
+            return idx;
+        }
+        let idx = StateIndex(self.nodes.len());
+        let mut node = TreeNode::from_state(canonical, turn);
+        node.index = Some(idx);
+        self.nodes.push(node);
+        self.table.insert(canonical, idx);
+        idx
+    }
+
+    /// Fully materializes every position up to `depth` plies from the root,
+    /// reusing nodes across transpositions.
+    pub fn explore(&mut self, depth: u32) {
+        let root = self.root_index;
+        self.explore_from(root, depth);
+    }
+
+    fn explore_from(&mut self, state: StateIndex, depth: u32) {
+        if depth == 0 || self.nodes[state.0].result != Result::Ongoing {
+            return;
+        }
+
+        let board = self.nodes[state.0].board;
+        let turn = self.nodes[state.0].turn;
+
+        for &col in COLUMN_ORDER.iter() {
+            let Some(next) = board.try_play(col, turn) else { continue };
+            let child = self.insert(next, turn.flip());
+            if !self.nodes[state.0].children.contains(&child) {
+                self.nodes[state.0].children.push(child);
+            }
+            self.explore_from(child, depth - 1);
+        }
+    }
+
+    pub fn count_children(&self) -> usize {
+        self.nodes.iter().map(|node| node.children.len()).sum()
+    }
+
+    pub fn iter_children<'a>(&'a self, state: &StateIndex) -> impl Iterator<Item = &'a StateIndex> {
+        self.nodes[state.0].children.iter()
+    }
+
+    /// A `Board`/`Grid` view of a node's packed position, for display and serialization.
+    pub fn get_board(&self, state: &StateIndex) -> Board {
+        self.nodes[state.0].board.to_board()
+    }
+
+    /// The state reached by playing `col` from `state`, if that move is legal
+    /// and its resulting position has been discovered anywhere in this tree
+    /// (not necessarily already linked as one of `state`'s own children).
+    pub fn child_for_column(&self, state: &StateIndex, col: usize) -> Option<StateIndex> {
+        let node = &self.nodes[state.0];
+        let next = node.board.try_play(col, node.turn)?;
+        self.table.get(&next.canonical()).copied()
+    }
+
+    /// `state`'s children as (column, state) pairs, for columns whose move
+    /// is both legal and already linked into the tree as a real child (as
+    /// opposed to `child_for_column`, which also matches transpositions).
+    pub fn children_with_columns<'a>(&'a self, state: &'a StateIndex) -> impl Iterator<Item = (usize, StateIndex)> + 'a {
+        (0..BOARD_SIZE.0).filter_map(move |col| {
+            let child = self.child_for_column(state, col)?;
+            self.nodes[state.0].children.contains(&child).then_some((col, child))
+        })
+    }
+
+    /// The sequence of columns played from the root to reach `target`, in
+    /// `Board::to_moves` notation and in the orientation of the root's stored
+    /// board, or `None` if `target` isn't linked as a descendant of the root
+    /// in this tree.
+    pub fn export_line(&self, target: StateIndex) -> Option<String> {
+        let mut columns = Vec::new();
+        self.find_path(self.root_index, target, false, &mut columns)
+            .then(|| columns.iter().map(usize::to_string).collect())
+    }
+
+    // `mirrored` says whether `state`'s stored (canonical) board is the mirror
+    // of the position actually reached in the root's orientation, so each
+    // column can be pushed in that orientation.
+    fn find_path(&self, state: StateIndex, target: StateIndex, mirrored: bool, columns: &mut Vec<usize>) -> bool {
+        if state == target {
+            return true;
+        }
+        let node = &self.nodes[state.0];
+        for (col, child) in self.children_with_columns(&state) {
+            let next = node.board.try_play(col, node.turn).unwrap();
+            let child_mirrored = mirrored != (next != self.nodes[child.0].board);
+            columns.push(if mirrored { BOARD_SIZE.0 - 1 - col } else { col });
+            if self.find_path(child, target, child_mirrored, columns) {
+                return true;
+            }
+            columns.pop();
+        }
+        false
+    }
+
+    /// Moves the navigation cursor to `current`'s child reached by playing
+    /// `col`, pushing `current` onto the breadcrumb stack. No-op (returns
+    /// `false`) if `col` isn't one of `current`'s linked children.
+    pub fn go_to_child(&mut self, col: usize) -> bool {
+        if !self.children_with_columns(&self.current).any(|(c, _)| c == col) {
+            return false;
+        }
+        let child = self.child_for_column(&self.current, col).unwrap();
+        self.path.push(self.current);
+        self.current = child;
+        true
+    }
+
+    /// Moves the cursor back to the last breadcrumb on the stack. No-op
+    /// (returns `false`) if already at the root of the visited path.
+    pub fn go_to_parent(&mut self) -> bool {
+        let Some(parent) = self.path.pop() else { return false };
+        self.current = parent;
+        true
+    }
+
+    /// Resets the cursor to the root, clearing the breadcrumb stack.
+    pub fn reset_to_root(&mut self) {
+        self.current = self.root_index;
+        self.path.clear();
+    }
+
+    /// Serializes the full annotated tree (every node, its result, children,
+    /// and MCTS stats) to JSON. The transposition table is rebuilt on load
+    /// rather than stored, since it's just an index over `nodes`.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&TreeExport { nodes: &self.nodes, root_index: self.root_index })
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Tree> {
+        let export: OwnedTreeExport = serde_json::from_str(json)?;
+        let mut table = HashMap::with_capacity(export.nodes.len());
+        for node in &export.nodes {
+            if let Some(idx) = node.index {
+                table.insert(node.board, idx);
+            }
+        }
+        Ok(Tree {
+            nodes: export.nodes,
+            root_index: export.root_index,
+            table,
+            current: export.root_index,
+            path: Vec::new(),
+        })
+    }
+
+    /// Rebuilds `table` from `nodes` from scratch, same as `from_json` does
+    /// on load. Needed any time `nodes` is remapped (e.g. pruning) without
+    /// going through `insert`, since `table` is just an index over it.
+    pub(crate) fn rebuild_table(&mut self) {
+        self.table = HashMap::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            if let Some(idx) = node.index {
+                self.table.insert(node.board, idx);
+            }
+        }
+    }
+
+    /// Solves already-explored nodes for their game-theoretic value via
+    /// negamax with alpha-beta pruning, visiting children in the order
+    /// `explore` linked them (`COLUMN_ORDER`) so the strongest cutoffs happen
+    /// first. Expects `explore` to have already materialized the subtree;
+    /// positions it didn't reach, positions an alpha-beta cutoff skipped, and
+    /// positions whose subtree bottoms out at the explore depth without a
+    /// terminal result are left out of the returned map rather than guessed at.
+    pub fn solve(&mut self) -> HashMap<StateIndex, Outcome> {
+        let mut memo = HashMap::new();
+        let root = self.root_index;
+        const MATE: i64 = 1_000_000;
+        self.negamax(root, 0, -MATE, MATE, &mut memo);
+        memo
+    }
+
+    /// Returns `None` when `state`'s value can't be determined from what's
+    /// been explored: a non-terminal node with no children is the frontier
+    /// of a bounded `explore`, not a proven loss, and a node is no better off
+    /// if any of its children are themselves undetermined (or were skipped
+    /// by pruning) — unless its best examined child is already a `Win`,
+    /// which no unexamined sibling could ever beat.
+    fn negamax(&self, state: StateIndex, depth: i64, mut alpha: i64, beta: i64, memo: &mut HashMap<StateIndex, Outcome>) -> Option<Outcome> {
+        if let Some(&outcome) = memo.get(&state) {
+            return Some(outcome);
+        }
+
+        let node = &self.nodes[state.0];
+        if node.result != Result::Ongoing {
+            // A terminal node's `turn` is whoever would have moved next, so any
+            // `Result::Win` here necessarily belongs to the other player.
+            let outcome = if node.result == Result::Draw { Outcome::Draw } else { Outcome::Loss(0) };
+            memo.insert(state, outcome);
+            return Some(outcome);
+        }
+        if node.children.is_empty() {
+            return None;
+        }
+
+        let children = node.children.clone();
+        let mut best: Option<Outcome> = None;
+        let mut saw_unresolved_child = false;
+        let mut pruned = false;
+        for child in children {
+            let Some(child_outcome) = self.negamax(child, depth + 1, -beta, -alpha, memo) else {
+                saw_unresolved_child = true;
+                continue;
+            };
+            let child_outcome = child_outcome.negate();
+            let better = match best {
+                Some(b) => child_outcome.at_least_as_good_as(&b),
+                None => true,
+            };
+            if better {
+                best = Some(child_outcome);
+            }
+            let Some(best_so_far) = best else { continue };
+            alpha = alpha.max(best_so_far.score_at(depth));
+            if alpha >= beta {
+                pruned = true;
+                break; // no sibling, however fast, can change the parent's choice from here
+            }
+        }
+
+        let best = best?;
+        // An unexamined sibling — skipped either because its own subtree was
+        // unresolved, or because pruning cut the loop short — could still be
+        // better than `best`, unless `best` is already a `Win` that nothing
+        // could beat. Otherwise this node's value is itself unresolved.
+        if (saw_unresolved_child || pruned) && !matches!(best, Outcome::Win(_)) {
+            return None;
+        }
+
+        memo.insert(state, best);
+        Some(best)
+    }
+
+    /// Grows the tree with `iterations` rounds of Monte Carlo Tree Search
+    /// from the root, an alternative to `explore` for positions too wide to
+    /// search exhaustively. Each round selects a path by UCT, expands one
+    /// untried move, rolls that out to a result with random play, and backs
+    /// the result up `visits`/`wins` along the exact path it selected (not
+    /// every parent of a transposed node).
+    pub fn mcts(&mut self, iterations: u32, exploration_c: f32) {
+        for _ in 0..iterations {
+            self.mcts_iteration(exploration_c);
+        }
+    }
+
+    fn mcts_iteration(&mut self, exploration_c: f32) {
+        let mut path = vec![self.root_index];
+
+        // Selection: descend by UCT until we hit a node with an untried move or the game ends.
+        let mut current = self.root_index;
+        while self.nodes[current.0].result == Result::Ongoing && !self.has_untried_move(current) {
+            current = self.select_uct_child(current, exploration_c);
+            path.push(current);
+        }
+
+        // Expansion: add one untried child, if the game isn't already over here.
+        if self.nodes[current.0].result == Result::Ongoing {
+            if let Some(child) = self.expand(current) {
+                path.push(child);
+                current = child;
+            }
+        }
+
+        let result = self.rollout(current);
+
+        for node in path {
+            let turn = self.nodes[node.0].turn;
+            self.nodes[node.0].visits += 1;
+            self.nodes[node.0].wins += Self::score_for(result, turn);
+        }
+    }
+
+    fn score_for(result: Result, turn: Player) -> f32 {
+        match result {
+            Result::Win(winner) if winner == turn => 1.0,
+            Result::Win(_) => 0.0,
+            Result::Draw => 0.5,
+            Result::Ongoing => unreachable!("rollout always resolves to a Win or Draw"),
+        }
+    }
+
+    fn select_uct_child(&self, state: StateIndex, exploration_c: f32) -> StateIndex {
+        let node = &self.nodes[state.0];
+        let parent_visits = node.visits.max(1) as f32;
+        node.children
+            .iter()
+            .copied()
+            .max_by(|&a, &b| {
+                self.uct_value(a, parent_visits, exploration_c)
+                    .partial_cmp(&self.uct_value(b, parent_visits, exploration_c))
+                    .unwrap()
+            })
+            .expect("select_uct_child called on a node with no children")
+    }
+
+    fn uct_value(&self, state: StateIndex, parent_visits: f32, exploration_c: f32) -> f32 {
+        let node = &self.nodes[state.0];
+        if node.visits == 0 {
+            return f32::INFINITY;
+        }
+        // `wins`/`visits` is scored from this child's own mover's perspective
+        // (see `score_for`), i.e. the opponent of whoever is choosing among
+        // children at the parent — flip it so selection maximizes the
+        // parent's win rate, not the opponent's.
+        let exploitation = 1.0 - node.wins / node.visits as f32;
+        let exploration = exploration_c * (parent_visits.ln() / node.visits as f32).sqrt();
+        exploitation + exploration
+    }
+
+    // Legal columns from `state` whose resulting position isn't already one of its children.
+    fn untried_columns(&self, state: StateIndex) -> impl Iterator<Item = usize> + '_ {
+        let node = &self.nodes[state.0];
+        let board = node.board.clone();
+        let turn = node.turn;
+        let children = node.children.clone();
+        COLUMN_ORDER.iter().copied().filter(move |&col| {
+            let Some(next) = board.try_play(col, turn) else { return false };
+            match self.table.get(&next.canonical()) {
+                Some(idx) => !children.contains(idx),
+                None => true,
+            }
+        })
+    }
+
+    fn has_untried_move(&self, state: StateIndex) -> bool {
+        self.untried_columns(state).next().is_some()
+    }
+
+    fn expand(&mut self, state: StateIndex) -> Option<StateIndex> {
+        let mut rng = rand::rng();
+        let col = self.untried_columns(state).choose(&mut rng)?;
+        let (board, turn) = {
+            let node = &self.nodes[state.0];
+            (node.board.clone(), node.turn)
+        };
+        let next = board.try_play(col, turn)?;
+        let child = self.insert(next, turn.flip());
+        self.nodes[state.0].children.push(child);
+        Some(child)
+    }
+
+    // Plays uniformly random legal moves from `state` until the result is decided.
+    fn rollout(&self, state: StateIndex) -> Result {
+        let node = &self.nodes[state.0];
+        if node.result != Result::Ongoing {
+            return node.result;
+        }
+
+        let mut board = node.board;
+        let mut turn = node.turn;
+        let mut rng = rand::rng();
+        loop {
+            let result = board.result();
+            if result != Result::Ongoing {
+                return result;
+            }
+            let col = (0..BOARD_SIZE.0)
+                .filter(|&c| board.try_play(c, turn).is_some())
+                .choose(&mut rng)
+                .expect("rollout reached a full board without a decided result");
+            board = board.try_play(col, turn).unwrap();
+            turn = turn.flip();
+        }
+    }
+}
+
+impl Index<&StateIndex> for Tree {
+    type Output = TreeNode;
+    fn index(&self, idx: &StateIndex) -> &TreeNode {
+        &self.nodes[idx.0]
+    }
+}
+
 #[allow(dead_code)]
 fn display_full_tree(start_node: &GameNode) {
     for child in start_node.children.iter() {
@@ -357,4 +1159,137 @@ fn main() {
     // println!("Random table entry:\n{}", table.values().next().unwrap());
 
 }
-*/
\ No newline at end of file
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terminal(result: Result) -> TreeNode {
+        let mut node = TreeNode::from_state(Bitboard::empty(), Player::Red);
+        node.result = result;
+        node
+    }
+
+    fn ongoing(children: Vec<StateIndex>) -> TreeNode {
+        let mut node = TreeNode::from_state(Bitboard::empty(), Player::Red);
+        node.children = children;
+        node
+    }
+
+    fn tree_with(nodes: Vec<TreeNode>) -> Tree {
+        Tree {
+            nodes,
+            root_index: StateIndex(0),
+            table: HashMap::new(),
+            current: StateIndex(0),
+            path: Vec::new(),
+        }
+    }
+
+    // Node 0 (root) has two children: node 1 is fully resolved two plies down
+    // to a forced Loss(2) for the root, and node 2 is an unexplored frontier
+    // node (no children, still Ongoing). An entire untried reply was never
+    // examined, so the root's value must stay undetermined even though its
+    // only resolved child already looks bad.
+    #[test]
+    fn solve_leaves_node_undetermined_when_a_sibling_is_unexplored() {
+        let tree = tree_with(vec![
+            ongoing(vec![StateIndex(1), StateIndex(2)]),
+            ongoing(vec![StateIndex(3)]),
+            ongoing(vec![]),
+            terminal(Result::Win(Player::Red)),
+        ]);
+
+        let mut memo = HashMap::new();
+        let root_outcome = tree.negamax(StateIndex(0), 0, -1_000_000, 1_000_000, &mut memo);
+        assert_eq!(root_outcome, None);
+        assert!(!memo.contains_key(&StateIndex(0)));
+    }
+
+    // Same shape, but the resolved child is already a Win for the root: no
+    // unexamined sibling could ever beat a win, so the root is determined
+    // even though node 2 was never explored.
+    #[test]
+    fn solve_trusts_a_resolved_win_even_with_an_unexplored_sibling() {
+        let tree = tree_with(vec![
+            ongoing(vec![StateIndex(1), StateIndex(2)]),
+            terminal(Result::Win(Player::Red)),
+            ongoing(vec![]),
+        ]);
+
+        let mut memo = HashMap::new();
+        let root_outcome = tree.negamax(StateIndex(0), 0, -1_000_000, 1_000_000, &mut memo);
+        assert_eq!(root_outcome, Some(Outcome::Win(1)));
+    }
+
+    // With every child fully resolved, the root is determined even though
+    // none of them are wins.
+    #[test]
+    fn solve_resolves_a_node_once_every_child_is_resolved() {
+        let tree = tree_with(vec![
+            ongoing(vec![StateIndex(1), StateIndex(2)]),
+            terminal(Result::Draw),
+            terminal(Result::Draw),
+        ]);
+
+        let mut memo = HashMap::new();
+        let root_outcome = tree.negamax(StateIndex(0), 0, -1_000_000, 1_000_000, &mut memo);
+        assert_eq!(root_outcome, Some(Outcome::Draw));
+    }
+
+    #[test]
+    fn outcome_negate_flips_value_and_extends_distance() {
+        assert_eq!(Outcome::Win(3).negate(), Outcome::Loss(4));
+        assert_eq!(Outcome::Loss(1).negate(), Outcome::Win(2));
+        assert_eq!(Outcome::Draw.negate(), Outcome::Draw);
+    }
+
+    // score_at anchors mate distance to absolute ply-from-root, so a child's
+    // score at depth+1 is always the exact negation of its parent's score at
+    // depth once negated into the parent's perspective.
+    #[test]
+    fn score_at_is_antisymmetric_across_a_ply() {
+        let child = Outcome::Win(2);
+        let parent = child.negate();
+        assert_eq!(parent.score_at(0), -child.score_at(1));
+    }
+
+    #[test]
+    fn bitboard_canonical_picks_the_lexicographically_smaller_orientation() {
+        let board = Bitboard::empty().try_play(0, Player::Red).unwrap();
+        let mirrored = board.mirror();
+        assert_eq!(board.canonical(), mirrored.canonical());
+        // Column 0 is bit 0, far below its mirror's column-6 bit, so the
+        // unmirrored board is the smaller orientation.
+        assert_eq!(board.canonical(), board);
+    }
+
+    // A board assembled one legal `play()` at a time, chosen so the greedy
+    // single-pass encoder dead-ends: an earlier ply can only be satisfied by
+    // reading an unrelated column's stack out of order. `to_moves` needs to
+    // backtrack past that kind of wrong guess rather than give up.
+    #[test]
+    fn to_moves_backtracks_past_a_greedy_dead_end() {
+        let mut board = Board::empty();
+        for &p in &[Player::Yellow, Player::Yellow, Player::Red] {
+            board.play(0, p);
+        }
+        for &p in &[Player::Red, Player::Yellow] {
+            board.play(1, p);
+        }
+        for &p in &[Player::Yellow, Player::Red, Player::Yellow] {
+            board.play(2, p);
+        }
+        for &p in &[Player::Red, Player::Yellow, Player::Red, Player::Red] {
+            board.play(4, p);
+        }
+        board.play(5, Player::Yellow);
+        for &p in &[Player::Red, Player::Yellow, Player::Red, Player::Yellow, Player::Red] {
+            board.play(6, p);
+        }
+
+        let moves = board.to_moves().expect("a board built from legal plays must encode");
+        assert_eq!(Board::from_moves(&moves), Some(board));
+    }
+}
\ No newline at end of file